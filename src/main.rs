@@ -7,6 +7,9 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 
+#[cfg(not(any(feature = "lang-ja", feature = "lang-en", feature = "lang-zh")))]
+compile_error!("少なくとも1つの言語パック機能 (lang-ja, lang-en, lang-zh) を有効にしてください。");
+
 /*
  * ============================================================================
  * Everling Semantic Integration (ESI) - v1.1.2 (デバッグ・同期強化版)
@@ -41,8 +44,14 @@ struct ExperimentConfig {
     integration_steps: usize,
     mode: DiscourseMode,
     noise_scale: f64,
-    alpha: f64, 
+    alpha: f64,
     seed_text: String,
+    /// 学習者向けの難易度上限 (1=N5相当の易しさ 〜 5=N1相当の難しさ)。Noneなら絞り込みなし。
+    max_level: Option<u8>,
+    /// 生成文を音声合成してresults/にWAVとして書き出すかどうか（日本語のみ対応）
+    synthesize_audio: bool,
+    /// 合成音声の話者ID（VOICEVOX等のバックエンドに渡される）
+    speaker_id: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -57,22 +66,99 @@ struct ResearchReport {
     config: ExperimentConfig,
     metrics: Vec<SimulationMetric>,
     generated_sentence: String,
-    variance_change: f64, 
+    variance_change: f64,
     intensity_score: f64,
+    /// `synthesize_audio` が有効な場合に書き出されたWAVファイルのパス
+    audio_path: Option<String>,
+}
+
+/// コーパスから学習したバイグラム連鎖モデル。
+/// `follows`: 単語 -> (後続語 -> 出現回数)。`starts`: 品詞カテゴリ -> (単語 -> 出現回数)。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TransitionModel {
+    follows: HashMap<String, HashMap<String, u32>>,
+    starts: HashMap<String, HashMap<String, u32>>,
+}
+
+impl TransitionModel {
+    const SMOOTHING: f64 = 0.1;
+
+    fn merge(&mut self, other: TransitionModel) {
+        for (word, succ) in other.follows {
+            let entry = self.follows.entry(word).or_default();
+            for (next, count) in succ {
+                *entry.entry(next).or_insert(0) += count;
+            }
+        }
+        for (category, dist) in other.starts {
+            let entry = self.starts.entry(category).or_default();
+            for (word, count) in dist {
+                *entry.entry(word).or_insert(0) += count;
+            }
+        }
+    }
+
+    /// `prev` の後に続く語を `pool` の中から学習済み分布でサンプリングする（加法平滑化あり）。
+    /// `prev` に後続語の記録が一切無い場合は `None` を返し、呼び出し側のフォールバックに委ねる。
+    fn sample_next(&self, prev: &str, pool: &[String], rng: &mut impl Rng) -> Option<String> {
+        let successors = self.follows.get(prev)?;
+        if successors.is_empty() || pool.is_empty() {
+            return None;
+        }
+        Self::weighted_pick(successors, pool, rng)
+    }
+
+    /// カテゴリの開始語分布から `pool` の中を学習済み頻度でサンプリングする。
+    fn weighted_start(&self, category: &str, pool: &[String], rng: &mut impl Rng) -> Option<String> {
+        let dist = self.starts.get(category)?;
+        if dist.is_empty() || pool.is_empty() {
+            return None;
+        }
+        Self::weighted_pick(dist, pool, rng)
+    }
+
+    fn weighted_pick(dist: &HashMap<String, u32>, pool: &[String], rng: &mut impl Rng) -> Option<String> {
+        let total: f64 = pool.iter().map(|w| *dist.get(w).unwrap_or(&0) as f64 + Self::SMOOTHING).sum();
+        let mut r = rng.gen_range(0.0..total);
+        for w in pool {
+            let weight = *dist.get(w).unwrap_or(&0) as f64 + Self::SMOOTHING;
+            if r < weight {
+                return Some(w.clone());
+            }
+            r -= weight;
+        }
+        pool.last().cloned()
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct VocabularyData {
     nouns: Vec<String>,
     particles: Vec<String>,
     verbs: Vec<String>,
     adverbs: Vec<String>,
+    /// 単語 -> TextRankによるコーパス内顕著性スコア。旧形式のJSONとの互換のためデフォルト空。
+    #[serde(default)]
+    scores: HashMap<String, f64>,
+    /// コーパスから学習したバイグラム連鎖モデル。旧形式のJSONとの互換のためデフォルト空。
+    #[serde(default)]
+    transitions: TransitionModel,
+    /// 単語 -> 学習者向け難易度 (1=N5相当の易しさ 〜 5=N1相当の難しさ)。旧形式のJSONとの互換のためデフォルト空。
+    #[serde(default)]
+    levels: HashMap<String, u8>,
+    /// BpeVocabBuilderが学習したマージ規則（形態素解析器を持たない言語向け）。旧形式のJSONとの互換のためデフォルト空。
+    #[serde(default)]
+    bpe_merges: Vec<(String, String)>,
+    /// 表記 -> 読み。MorphemeProcessorが固有名詞（品詞"固有名詞"）に読み仮名を見つけた場合にここへ蓄積する。
+    /// 旧形式のJSONとの互換のためデフォルト空。
+    #[serde(default)]
+    proper_noun_readings: HashMap<String, String>,
 }
 
 impl VocabularyData {
     #[allow(dead_code)]
     fn empty() -> Self {
-        VocabularyData { nouns: vec![], particles: vec![], verbs: vec![], adverbs: vec![] }
+        VocabularyData { nouns: vec![], particles: vec![], verbs: vec![], adverbs: vec![], scores: HashMap::new(), transitions: TransitionModel::default(), levels: HashMap::new(), bpe_merges: Vec::new(), proper_noun_readings: HashMap::new() }
     }
 
     /// 別の語彙データを自身にマージし、重複を排除する
@@ -91,16 +177,112 @@ impl VocabularyData {
         self.particles = p.into_iter().collect();
         self.verbs = v.into_iter().collect();
         self.adverbs = a.into_iter().collect();
+
+        for (word, score) in other.scores {
+            let entry = self.scores.entry(word).or_insert(0.0);
+            if score > *entry {
+                *entry = score;
+            }
+        }
+
+        self.transitions.merge(other.transitions);
+
+        self.levels.extend(other.levels);
+
+        self.bpe_merges.extend(other.bpe_merges);
+
+        self.proper_noun_readings.extend(other.proper_noun_readings);
+
+        self.sort_by_salience();
+    }
+
+    /// 各品詞カテゴリをTextRankスコアの降順に並べ替える。
+    /// スコアを持たない単語（旧データ由来など）は末尾に残る。
+    fn sort_by_salience(&mut self) {
+        let score_of = |scores: &HashMap<String, f64>, w: &str| scores.get(w).copied().unwrap_or(0.0);
+        self.nouns.sort_by(|a, b| score_of(&self.scores, b).partial_cmp(&score_of(&self.scores, a)).unwrap());
+        self.particles.sort_by(|a, b| score_of(&self.scores, b).partial_cmp(&score_of(&self.scores, a)).unwrap());
+        self.verbs.sort_by(|a, b| score_of(&self.scores, b).partial_cmp(&score_of(&self.scores, a)).unwrap());
+        self.adverbs.sort_by(|a, b| score_of(&self.scores, b).partial_cmp(&score_of(&self.scores, a)).unwrap());
+    }
+
+    /// `grader` を使い、全カテゴリの単語に難易度を付与する。既存の等級は上書きする。
+    fn annotate_levels(&mut self, grader: &DifficultyGrader) {
+        for word in self.nouns.iter().chain(self.particles.iter()).chain(self.verbs.iter()).chain(self.adverbs.iter()) {
+            if let Some(level) = grader.level_of(word) {
+                self.levels.insert(word.clone(), level);
+            }
+        }
+    }
+
+    /// `max_level` より難しいと判定された単語を各カテゴリから取り除く。
+    /// 等級が不明な単語（等級データ未ロード時など）は絞り込み対象にしない。
+    fn filter_by_level(&mut self, max_level: u8) {
+        let levels = self.levels.clone();
+        let keep = move |w: &String| levels.get(w).is_none_or(|&lv| lv <= max_level);
+        self.nouns.retain(keep.clone());
+        self.particles.retain(keep.clone());
+        self.verbs.retain(keep.clone());
+        self.adverbs.retain(keep);
     }
 }
 
+// 言語パックは Cargo フィーチャ (`lang-ja` / `lang-en` / `lang-zh`) で有効・無効を切り替える。
+// 無効化された言語のデフォルト語彙・同期処理・永続化フィールドはバイナリに一切含まれない。
+// 希少語・難読語などの追加層は `scope-archaic` フィーチャ（既定は `scope-common` のみ）で制御する。
 #[derive(Debug, Serialize, Deserialize)]
 struct LanguageVocabularies {
+    /// フィーチャが無効なビルドが書き出したJSONには存在しないため、デフォルト空で補う。
+    #[cfg(feature = "lang-en")]
+    #[serde(default)]
     english: VocabularyData,
+    #[cfg(feature = "lang-ja")]
+    #[serde(default)]
     japanese: VocabularyData,
+    #[cfg(feature = "lang-zh")]
+    #[serde(default)]
     chinese: VocabularyData,
 }
 
+impl LanguageVocabularies {
+    /// `language` に対応する語彙を取り出す。対応する言語パックのフィーチャが
+    /// 無効な場合は、どのフィーチャを有効にすべきかを示すエラーを返す。
+    fn take(self, language: Language) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        match language {
+            Language::English => Self::take_english(self),
+            Language::Japanese => Self::take_japanese(self),
+            Language::Chinese => Self::take_chinese(self),
+        }
+    }
+
+    #[cfg(feature = "lang-en")]
+    fn take_english(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Ok(self.english)
+    }
+    #[cfg(not(feature = "lang-en"))]
+    fn take_english(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Err("言語パック 'lang-en' は無効化されています。Cargo.toml の features に 'lang-en' を追加してください。".into())
+    }
+
+    #[cfg(feature = "lang-ja")]
+    fn take_japanese(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Ok(self.japanese)
+    }
+    #[cfg(not(feature = "lang-ja"))]
+    fn take_japanese(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Err("言語パック 'lang-ja' は無効化されています。Cargo.toml の features に 'lang-ja' を追加してください。".into())
+    }
+
+    #[cfg(feature = "lang-zh")]
+    fn take_chinese(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Ok(self.chinese)
+    }
+    #[cfg(not(feature = "lang-zh"))]
+    fn take_chinese(self) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+        Err("言語パック 'lang-zh' は無効化されています。Cargo.toml の features に 'lang-zh' を追加してください。".into())
+    }
+}
+
 // ==================== 数学エンジン ====================
 
 struct EverlingIntegrator {
@@ -139,46 +321,702 @@ impl MorphemeProcessor {
         let mut particles = HashSet::new();
         let mut verbs = HashSet::new();
         let mut adverbs = HashSet::new();
+        let mut proper_noun_readings = HashMap::new();
         let mut line_count = 0;
+        let mut tagged: Vec<(String, &'static str)> = Vec::new();
 
         for line in reader.lines().skip(1) {
             let line = line?;
             line_count += 1;
-            
+
             // カンマまたはタブで分割を試みる
             let parts: Vec<&str> = if line.contains(',') {
                 line.split(',').collect()
             } else {
                 line.split('\t').collect()
             };
-            
-            // UniDicカラム想定: 2:表層形, 6:品詞
+
+            // UniDicカラム想定: 2:表層形, 6:品詞, 9:発音形出現形（任意、固有名詞の読み仮名として利用）
             if parts.len() > 6 {
                 let word = parts[2].trim().replace("\"", "");
                 if word.is_empty() { continue; }
                 let pos = parts[6];
 
-                if pos.contains("名詞") || pos.contains("代名詞") || pos.contains("接尾辞") {
-                    nouns.insert(word);
+                let category = if pos.contains("固有名詞") {
+                    nouns.insert(word.clone());
+                    if parts.len() > 9 {
+                        let reading = parts[9].trim().replace("\"", "");
+                        if !reading.is_empty() && reading != word {
+                            proper_noun_readings.insert(word.clone(), reading);
+                        }
+                    }
+                    "noun"
+                } else if pos.contains("名詞") || pos.contains("代名詞") || pos.contains("接尾辞") {
+                    nouns.insert(word.clone());
+                    "noun"
                 } else if pos.contains("助詞") {
-                    particles.insert(word);
+                    particles.insert(word.clone());
+                    "particle"
                 } else if pos.contains("動詞") {
-                    verbs.insert(word);
+                    verbs.insert(word.clone());
+                    "verb"
                 } else if pos.contains("副詞") {
-                    adverbs.insert(word);
-                }
+                    adverbs.insert(word.clone());
+                    "adverb"
+                } else {
+                    continue;
+                };
+                tagged.push((word, category));
             }
         }
 
         println!("[Debug] {} 行のデータを走査しました。", line_count);
 
+        let words: Vec<String> = tagged.iter().map(|(w, _)| w.clone()).collect();
+        let scores = Self::compute_textrank(&words);
+        let transitions = Self::build_transitions(&tagged);
+        Ok(VocabularyData {
+            nouns: nouns.into_iter().collect(),
+            particles: particles.into_iter().collect(),
+            verbs: verbs.into_iter().collect(),
+            adverbs: adverbs.into_iter().collect(),
+            scores,
+            transitions,
+            levels: HashMap::new(),
+            bpe_merges: Vec::new(),
+            proper_noun_readings,
+        })
+    }
+
+    /// 未分割の中国語テキストを辞書ベースの分かち書きで語彙化する。
+    /// UniDicのようなカラム付きコーパスが無い中国語向けの代替パス。
+    #[cfg(feature = "lang-zh")]
+    fn process_chinese_text(path: &str) -> io::Result<VocabularyData> {
+        let raw = fs::read_to_string(path)?;
+        let segmenter = ChineseSegmenter::new();
+
+        let mut nouns = HashSet::new();
+        let mut particles = HashSet::new();
+        let mut verbs = HashSet::new();
+        let mut adverbs = HashSet::new();
+        let mut tagged: Vec<(String, &'static str)> = Vec::new();
+
+        for sentence in raw.split(|c: char| "。！？\n".contains(c)) {
+            let chars: Vec<char> = sentence.chars().filter(|c| !c.is_whitespace()).collect();
+            if chars.is_empty() {
+                continue;
+            }
+            for (segment, pos) in segmenter.cut(&chars) {
+                let word: String = segment.iter().collect();
+                let category = match pos {
+                    "v" => { verbs.insert(word.clone()); "verb" }
+                    "p" => { particles.insert(word.clone()); "particle" }
+                    "d" => { adverbs.insert(word.clone()); "adverb" }
+                    _ => { nouns.insert(word.clone()); "noun" }
+                };
+                tagged.push((word, category));
+            }
+        }
+
+        println!("[Debug] 中国語コーパス '{}' を分かち書きしました。", path);
+
+        let words: Vec<String> = tagged.iter().map(|(w, _)| w.clone()).collect();
+        let scores = Self::compute_textrank(&words);
+        let transitions = Self::build_transitions(&tagged);
         Ok(VocabularyData {
             nouns: nouns.into_iter().collect(),
             particles: particles.into_iter().collect(),
             verbs: verbs.into_iter().collect(),
             adverbs: adverbs.into_iter().collect(),
+            scores,
+            transitions,
+            levels: HashMap::new(),
+            bpe_merges: Vec::new(),
+            proper_noun_readings: HashMap::new(),
         })
     }
+
+    /// TextRankによる単語の顕著性スコアを計算する。
+    /// ウィンドウ幅4の共起グラフを構築し、減衰率0.85で、スコアの変化が1e-4未満になるか最大25回に達するまで反復する。
+    fn compute_textrank(tokens: &[String]) -> HashMap<String, f64> {
+        const WINDOW: usize = 4;
+        const DAMPING: f64 = 0.85;
+        const MAX_ITER: usize = 25;
+        const EPSILON: f64 = 1e-4;
+
+        if tokens.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut edge_weights: HashMap<(String, String), f64> = HashMap::new();
+        for i in 0..tokens.len() {
+            for j in (i + 1)..tokens.len().min(i + WINDOW) {
+                if tokens[i] == tokens[j] {
+                    continue;
+                }
+                let key = if tokens[i] <= tokens[j] {
+                    (tokens[i].clone(), tokens[j].clone())
+                } else {
+                    (tokens[j].clone(), tokens[i].clone())
+                };
+                *edge_weights.entry(key).or_insert(0.0) += 1.0;
+            }
+        }
+
+        let mut neighbors: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+        for ((a, b), w) in &edge_weights {
+            neighbors.entry(a.clone()).or_default().push((b.clone(), *w));
+            neighbors.entry(b.clone()).or_default().push((a.clone(), *w));
+        }
+
+        let out_weight: HashMap<String, f64> = neighbors.iter()
+            .map(|(node, edges)| (node.clone(), edges.iter().map(|(_, w)| w).sum()))
+            .collect();
+
+        let nodes: HashSet<String> = tokens.iter().cloned().collect();
+        let mut scores: HashMap<String, f64> = nodes.iter().map(|n| (n.clone(), 1.0)).collect();
+
+        for _ in 0..MAX_ITER {
+            let mut next = HashMap::with_capacity(scores.len());
+            let mut max_delta = 0.0_f64;
+            for node in &nodes {
+                let mut incoming = 0.0;
+                if let Some(edges) = neighbors.get(node) {
+                    for (other, w) in edges {
+                        let denom = out_weight.get(other).copied().unwrap_or(1.0).max(1e-9);
+                        incoming += (w / denom) * scores.get(other).copied().unwrap_or(1.0);
+                    }
+                }
+                let s = (1.0 - DAMPING) + DAMPING * incoming;
+                max_delta = max_delta.max((s - scores[node]).abs());
+                next.insert(node.clone(), s);
+            }
+            scores = next;
+            if max_delta < EPSILON {
+                break;
+            }
+        }
+
+        scores
+    }
+
+    /// コーパス中の出現順 `(単語, カテゴリ)` 列からバイグラム連鎖モデルを学習する。
+    fn build_transitions(tagged: &[(String, &'static str)]) -> TransitionModel {
+        let mut follows: HashMap<String, HashMap<String, u32>> = HashMap::new();
+        let mut starts: HashMap<String, HashMap<String, u32>> = HashMap::new();
+
+        for (word, category) in tagged {
+            *starts.entry(category.to_string()).or_default().entry(word.clone()).or_insert(0) += 1;
+        }
+        for pair in tagged.windows(2) {
+            let (prev, _) = &pair[0];
+            let (next, _) = &pair[1];
+            *follows.entry(prev.clone()).or_default().entry(next.clone()).or_insert(0) += 1;
+        }
+
+        TransitionModel { follows, starts }
+    }
+}
+
+// ==================== 中国語分かち書き ====================
+
+/// 簡易辞書エントリ: (表層形, 出現頻度, 品詞タグ)
+/// タグは jieba 互換の簡略版 (n=名詞, v=動詞, p=介詞/助詞, d=副詞)
+#[cfg(feature = "lang-zh")]
+const CN_DICT: &[(&str, u32, &str)] = &[
+    ("的", 800000, "p"),
+    ("了", 300000, "p"),
+    ("是", 250000, "v"),
+    ("在", 220000, "p"),
+    ("我", 200000, "n"),
+    ("你", 180000, "n"),
+    ("他", 150000, "n"),
+    ("们", 90000, "p"),
+    ("这", 160000, "n"),
+    ("那", 140000, "n"),
+    ("和", 120000, "p"),
+    ("静", 5000, "n"),
+    ("宁静", 8000, "n"),
+    ("思考", 6000, "n"),
+    ("技术", 9000, "n"),
+    ("日常", 7000, "n"),
+    ("漂流", 4000, "v"),
+    ("共鸣", 3000, "v"),
+    ("加速", 3500, "v"),
+    ("渐渐", 4500, "d"),
+    ("突然", 5000, "d"),
+    ("温柔", 3000, "d"),
+    ("地", 60000, "p"),
+];
+
+#[cfg(feature = "lang-zh")]
+struct ChineseSegmenter {
+    /// 表層形 -> (頻度合計, 品詞)
+    prefix_dict: HashMap<String, (u32, &'static str)>,
+    total_freq: u64,
+}
+
+#[cfg(feature = "lang-zh")]
+impl ChineseSegmenter {
+    fn new() -> Self {
+        let mut prefix_dict = HashMap::new();
+        let mut total_freq: u64 = 0;
+        for &(word, freq, pos) in CN_DICT {
+            prefix_dict.insert(word.to_string(), (freq, pos));
+            total_freq += freq as u64;
+        }
+        ChineseSegmenter { prefix_dict, total_freq }
+    }
+
+    /// 最大確率パスによる分かち書き (DAGカット)。
+    /// route[i] = chars[i..] を最適に分割した場合の対数確率の総和
+    fn cut<'a>(&self, sentence: &'a [char]) -> Vec<(&'a [char], &'static str)> {
+        let n = sentence.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let min_log = (1.0_f64 / self.total_freq.max(1) as f64).ln();
+
+        // dag[i] = i から始まり辞書に存在する単語の終端インデックス一覧
+        let mut dag: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for i in 0..n {
+            dag[i].push(i + 1); // 単漢字は常に候補（辞書に無くても fallback）
+            for j in (i + 1)..n {
+                let candidate: String = sentence[i..=j].iter().collect();
+                if self.prefix_dict.contains_key(&candidate) {
+                    dag[i].push(j + 1);
+                }
+            }
+        }
+
+        let mut route = vec![0.0_f64; n + 1];
+        for i in (0..n).rev() {
+            let mut best = f64::NEG_INFINITY;
+            for &j in &dag[i] {
+                let word: String = sentence[i..j].iter().collect();
+                let log_freq = match self.prefix_dict.get(&word) {
+                    Some(&(freq, _)) => (freq as f64 / self.total_freq as f64).ln(),
+                    None => min_log,
+                };
+                let score = log_freq + route[j];
+                if score > best {
+                    best = score;
+                }
+            }
+            route[i] = best;
+        }
+
+        let mut segments = Vec::new();
+        let mut i = 0;
+        while i < n {
+            let mut best_j = i + 1;
+            let mut best_score = f64::NEG_INFINITY;
+            for &j in &dag[i] {
+                let word: String = sentence[i..j].iter().collect();
+                let log_freq = match self.prefix_dict.get(&word) {
+                    Some(&(freq, _)) => (freq as f64 / self.total_freq as f64).ln(),
+                    None => min_log,
+                };
+                let score = log_freq + route[j];
+                if score > best_score {
+                    best_score = score;
+                    best_j = j;
+                }
+            }
+            let pos = self.prefix_dict.get(&sentence[i..best_j].iter().collect::<String>())
+                .map(|&(_, pos)| pos)
+                .unwrap_or("n"); // 辞書に無い単漢字は名詞扱いでフォールバック
+            segments.push((&sentence[i..best_j], pos));
+            i = best_j;
+        }
+        segments
+    }
+}
+
+#[cfg(all(test, feature = "lang-zh"))]
+mod chinese_segmenter_tests {
+    use super::ChineseSegmenter;
+
+    #[test]
+    fn cut_splits_known_words_by_dictionary() {
+        let segmenter = ChineseSegmenter::new();
+        let chars: Vec<char> = "我是你".chars().collect();
+        let segments: Vec<String> = segmenter.cut(&chars)
+            .into_iter()
+            .map(|(seg, _)| seg.iter().collect())
+            .collect();
+        assert_eq!(segments, vec!["我".to_string(), "是".to_string(), "你".to_string()]);
+    }
+}
+
+// ==================== BPEサブワード学習 ====================
+
+/// 形態素解析器を持たない言語（英語など）向けに、生テキストから直接
+/// バイトペア・エンコーディング (BPE) でサブワード語彙を学習する。
+#[cfg(feature = "lang-en")]
+struct BpeVocabBuilder {
+    vocab_size: usize,
+    min_pair_freq: u32,
+}
+
+#[cfg(feature = "lang-en")]
+impl BpeVocabBuilder {
+    fn new(vocab_size: usize, min_pair_freq: u32) -> Self {
+        BpeVocabBuilder { vocab_size, min_pair_freq }
+    }
+
+    /// 単語頻度から開始し、最も頻出するシンボル対を `vocab_size` 回または
+    /// 最大頻度が `min_pair_freq` を下回るまで逐次マージしてゆく。
+    fn train(&self, word_freq: &HashMap<String, u32>) -> (Vec<(String, String)>, HashMap<String, u32>) {
+        let mut corpus: Vec<(Vec<String>, u32)> = word_freq.iter()
+            .map(|(word, &freq)| {
+                let mut symbols: Vec<String> = word.chars().map(|c| c.to_string()).collect();
+                symbols.push("</w>".to_string());
+                (symbols, freq)
+            })
+            .collect();
+
+        let mut merges = Vec::new();
+
+        while merges.len() < self.vocab_size {
+            let mut pair_freq: HashMap<(String, String), u32> = HashMap::new();
+            for (symbols, freq) in &corpus {
+                for pair in symbols.windows(2) {
+                    *pair_freq.entry((pair[0].clone(), pair[1].clone())).or_insert(0) += freq;
+                }
+            }
+
+            let best = pair_freq.into_iter().max_by_key(|(_, count)| *count);
+            let (best_pair, count) = match best {
+                Some(x) => x,
+                None => break,
+            };
+            if count < self.min_pair_freq {
+                break;
+            }
+
+            let merged_symbol = format!("{}{}", best_pair.0, best_pair.1);
+            for (symbols, _) in corpus.iter_mut() {
+                *symbols = Self::apply_merge(symbols, &best_pair, &merged_symbol);
+            }
+            merges.push(best_pair);
+        }
+
+        let mut token_freq: HashMap<String, u32> = HashMap::new();
+        for (symbols, freq) in &corpus {
+            for symbol in symbols {
+                let token = symbol.trim_end_matches("</w>");
+                if token.is_empty() {
+                    continue;
+                }
+                *token_freq.entry(token.to_string()).or_insert(0) += freq;
+            }
+        }
+
+        (merges, token_freq)
+    }
+
+    fn apply_merge(symbols: &[String], pair: &(String, String), merged_symbol: &str) -> Vec<String> {
+        let mut result = Vec::with_capacity(symbols.len());
+        let mut i = 0;
+        while i < symbols.len() {
+            if i + 1 < symbols.len() && symbols[i] == pair.0 && symbols[i + 1] == pair.1 {
+                result.push(merged_symbol.to_string());
+                i += 2;
+            } else {
+                result.push(symbols[i].clone());
+                i += 1;
+            }
+        }
+        result
+    }
+
+    /// 生テキストから単語頻度を数え、BPEを学習してVocabularyDataを構築する。
+    /// 頻出する（＝マージによって育った）部分語ほど内容語らしいとみなし `nouns` に収める。
+    fn train_on_text(&self, text: &str) -> VocabularyData {
+        let mut word_freq: HashMap<String, u32> = HashMap::new();
+        for word in text.split_whitespace() {
+            let cleaned: String = word.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+            if cleaned.is_empty() {
+                continue;
+            }
+            *word_freq.entry(cleaned).or_insert(0) += 1;
+        }
+
+        let (merges, token_freq) = self.train(&word_freq);
+
+        let mut tokens: Vec<(String, u32)> = token_freq.into_iter().filter(|(t, _)| t.chars().count() >= 2).collect();
+        tokens.sort_by_key(|(_, freq)| std::cmp::Reverse(*freq));
+        let scores: HashMap<String, f64> = tokens.iter().map(|(t, f)| (t.clone(), *f as f64)).collect();
+        let nouns: Vec<String> = tokens.into_iter().map(|(t, _)| t).collect();
+
+        VocabularyData {
+            nouns,
+            particles: vec![],
+            verbs: vec![],
+            adverbs: vec![],
+            scores,
+            transitions: TransitionModel::default(),
+            levels: HashMap::new(),
+            bpe_merges: merges,
+            proper_noun_readings: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "lang-en"))]
+mod bpe_vocab_builder_tests {
+    use super::BpeVocabBuilder;
+    use std::collections::HashMap;
+
+    #[test]
+    fn train_merges_most_frequent_pair_first() {
+        let builder = BpeVocabBuilder::new(1, 1);
+        let mut word_freq = HashMap::new();
+        word_freq.insert("aaab".to_string(), 10);
+
+        let (merges, _token_freq) = builder.train(&word_freq);
+
+        assert_eq!(merges, vec![("a".to_string(), "a".to_string())]);
+    }
+}
+
+// ==================== 難易度分析 ====================
+
+/// 学習者向け語彙の難易度を判定する。
+/// 内部スケールは 1(N5相当/易しい) 〜 5(N1相当/難しい)。
+struct DifficultyGrader {
+    /// 漢字(またはその他の文字) -> 等級
+    kanji_grades: HashMap<char, u8>,
+    /// 単語 -> JLPT語彙リストに記載された明示的な等級
+    jlpt_vocab: HashMap<String, u8>,
+}
+
+impl DifficultyGrader {
+    /// 等級ファイルが存在しない場合はそのカテゴリの判定材料を持たないグレーダーになる。
+    fn load(kanji_grade_path: &str, jlpt_vocab_path: &str) -> io::Result<Self> {
+        let kanji_grades = if Path::new(kanji_grade_path).exists() {
+            Self::parse_char_levels(kanji_grade_path)?
+        } else {
+            HashMap::new()
+        };
+        let jlpt_vocab = if Path::new(jlpt_vocab_path).exists() {
+            Self::parse_word_levels(jlpt_vocab_path)?
+        } else {
+            HashMap::new()
+        };
+        Ok(DifficultyGrader { kanji_grades, jlpt_vocab })
+    }
+
+    fn parse_char_levels(path: &str) -> io::Result<HashMap<char, u8>> {
+        let mut map = HashMap::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let parts: Vec<&str> = if line.contains(',') { line.split(',').collect() } else { line.split('\t').collect() };
+            if parts.len() < 2 { continue; }
+            let ch = match parts[0].trim().chars().next() {
+                Some(c) => c,
+                None => continue,
+            };
+            if let Some(level) = Self::parse_level(parts[1]) {
+                map.insert(ch, level);
+            }
+        }
+        Ok(map)
+    }
+
+    fn parse_word_levels(path: &str) -> io::Result<HashMap<String, u8>> {
+        let mut map = HashMap::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let parts: Vec<&str> = if line.contains(',') { line.split(',').collect() } else { line.split('\t').collect() };
+            if parts.len() < 2 { continue; }
+            let word = parts[0].trim().to_string();
+            if word.is_empty() { continue; }
+            if let Some(level) = Self::parse_level(parts[1]) {
+                map.insert(word, level);
+            }
+        }
+        Ok(map)
+    }
+
+    /// "N5"〜"N1" のようなJLPT表記を内部スケール 1(易)〜5(難) に変換する
+    fn parse_level(raw: &str) -> Option<u8> {
+        match raw.trim().to_uppercase().as_str() {
+            "N5" => Some(1),
+            "N4" => Some(2),
+            "N3" => Some(3),
+            "N2" => Some(4),
+            "N1" => Some(5),
+            _ => None,
+        }
+    }
+
+    /// JLPT語彙リストに明示的な等級があればそれを採用し、無ければ単語に含まれる
+    /// 漢字のうち最も難しい等級を採用する。どちらの情報も無ければ `None`（等級不明）。
+    fn level_of(&self, word: &str) -> Option<u8> {
+        if let Some(&level) = self.jlpt_vocab.get(word) {
+            return Some(level);
+        }
+        word.chars().filter_map(|c| self.kanji_grades.get(&c).copied()).max()
+    }
+}
+
+#[cfg(test)]
+mod difficulty_grader_tests {
+    use super::DifficultyGrader;
+
+    #[test]
+    fn parse_level_maps_jlpt_notation_to_internal_scale() {
+        assert_eq!(DifficultyGrader::parse_level("N5"), Some(1));
+        assert_eq!(DifficultyGrader::parse_level("n1"), Some(5));
+        assert_eq!(DifficultyGrader::parse_level(" N3 "), Some(3));
+        assert_eq!(DifficultyGrader::parse_level("unknown"), None);
+    }
+}
+
+// ==================== 音声合成 ====================
+
+/// 1モーラの情報。`pitch` は簡易アクセントモデルでの相対的な高さ、`is_pause` は無音区間を示す。
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+struct Mora {
+    text: String,
+    pitch: f32,
+    is_pause: bool,
+}
+
+/// 読点・句点で区切られる単位。VOICEVOX等のアクセント句に相当する。
+#[derive(Debug, Clone)]
+struct AccentPhrase {
+    moras: Vec<Mora>,
+}
+
+/// 生成文をモーラ列（アクセント句の並び）へ変換する簡易コンバータ。
+/// 句読点をポーズとして扱い、各アクセント句には「頭低・中高」型の簡易ピッチを付与する。
+struct AccentPhraseConverter;
+
+impl AccentPhraseConverter {
+    fn convert(text: &str) -> Vec<AccentPhrase> {
+        let mut phrases = Vec::new();
+        let mut current = Vec::new();
+
+        for ch in text.chars() {
+            if "、。".contains(ch) {
+                if !current.is_empty() {
+                    phrases.push(AccentPhrase { moras: std::mem::take(&mut current) });
+                }
+                phrases.push(AccentPhrase { moras: vec![Mora { text: "pau".into(), pitch: 0.0, is_pause: true }] });
+                continue;
+            }
+            if ch.is_whitespace() {
+                continue;
+            }
+            current.push(Mora { text: ch.to_string(), pitch: 0.0, is_pause: false });
+        }
+        if !current.is_empty() {
+            phrases.push(AccentPhrase { moras: current });
+        }
+
+        Self::apply_pitch_accent(&mut phrases);
+        phrases
+    }
+
+    /// 各アクセント句の1モーラ目を低く、以降をピークから緩やかに下降させる頭低・中高型を既定とする。
+    fn apply_pitch_accent(phrases: &mut [AccentPhrase]) {
+        for phrase in phrases.iter_mut() {
+            let n = phrase.moras.len();
+            for (i, mora) in phrase.moras.iter_mut().enumerate() {
+                if mora.is_pause {
+                    continue;
+                }
+                mora.pitch = if n <= 1 {
+                    5.0
+                } else if i == 0 {
+                    3.0
+                } else {
+                    5.0 - (i as f32 / n as f32) * 2.0
+                };
+            }
+        }
+    }
+}
+
+/// 音声合成バックエンドの差し替え口。実機のVOICEVOX等のHTTP API実装をここに差し込む。
+trait SynthesisBackend {
+    fn synthesize(&self, phrases: &[AccentPhrase], speaker_id: u32) -> io::Result<Vec<u8>>;
+}
+
+/// 実エンジン未接続時のプレースホルダー実装。モーラ数に応じた長さの無音WAVを生成する。
+struct SilentWavBackend;
+
+impl SynthesisBackend for SilentWavBackend {
+    fn synthesize(&self, phrases: &[AccentPhrase], _speaker_id: u32) -> io::Result<Vec<u8>> {
+        const SAMPLE_RATE: u32 = 24000;
+        const MORA_MILLIS: u32 = 120;
+
+        let mora_count: u32 = phrases.iter().map(|p| p.moras.len() as u32).sum();
+        let num_samples = mora_count.max(1) * MORA_MILLIS * SAMPLE_RATE / 1000;
+        Ok(Self::build_silent_wav(SAMPLE_RATE, num_samples))
+    }
+}
+
+impl SilentWavBackend {
+    /// 16bit PCM モノラルの無音WAVを組み立てる
+    fn build_silent_wav(sample_rate: u32, num_samples: u32) -> Vec<u8> {
+        let data_size = num_samples * 2;
+        let mut buf = Vec::with_capacity(44 + data_size as usize);
+
+        buf.extend_from_slice(b"RIFF");
+        buf.extend_from_slice(&(36 + data_size).to_le_bytes());
+        buf.extend_from_slice(b"WAVE");
+        buf.extend_from_slice(b"fmt ");
+        buf.extend_from_slice(&16u32.to_le_bytes());
+        buf.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        buf.extend_from_slice(&1u16.to_le_bytes()); // モノラル
+        buf.extend_from_slice(&sample_rate.to_le_bytes());
+        buf.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // バイトレート
+        buf.extend_from_slice(&2u16.to_le_bytes()); // ブロックアライン
+        buf.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+        buf.extend_from_slice(b"data");
+        buf.extend_from_slice(&data_size.to_le_bytes());
+        buf.resize(buf.len() + data_size as usize, 0);
+
+        buf
+    }
+}
+
+/// 生成文を音声化するパイプライン。読み上げ前にユーザー辞書で固有名詞の読みを上書きする。
+struct SpeechSynthesizer {
+    backend: Box<dyn SynthesisBackend>,
+    /// 表記 -> 読み。MorphemeProcessorが抽出したコーパス固有の固有名詞をここに登録できる。
+    user_dictionary: HashMap<String, String>,
+}
+
+impl SpeechSynthesizer {
+    fn new(backend: Box<dyn SynthesisBackend>) -> Self {
+        SpeechSynthesizer { backend, user_dictionary: HashMap::new() }
+    }
+
+    /// コーパス由来の固有名詞に独自の読みを登録する
+    fn register_reading(&mut self, surface: &str, reading: &str) {
+        self.user_dictionary.insert(surface.to_string(), reading.to_string());
+    }
+
+    fn apply_user_dictionary(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for (surface, reading) in &self.user_dictionary {
+            result = result.replace(surface.as_str(), reading.as_str());
+        }
+        result
+    }
+
+    fn synthesize_to_file(&self, text: &str, speaker_id: u32, output_path: &Path) -> io::Result<()> {
+        let normalized = self.apply_user_dictionary(text);
+        let phrases = AccentPhraseConverter::convert(&normalized);
+        let wav = self.backend.synthesize(&phrases, speaker_id)?;
+        fs::write(output_path, wav)
+    }
 }
 
 // ==================== 言語合成エンジン ====================
@@ -189,14 +1027,14 @@ struct LinguisticAssembler {
 }
 
 impl LinguisticAssembler {
-    fn new(language: Language) -> Result<Self, Box<dyn std::error::Error>> {
-        let vocab = Self::load_and_sync_vocabulary(language)?;
+    fn new(language: Language, max_level: Option<u8>) -> Result<Self, Box<dyn std::error::Error>> {
+        let vocab = Self::load_and_sync_vocabulary(language, max_level)?;
         Ok(LinguisticAssembler { language, vocab })
     }
 
-    fn load_and_sync_vocabulary(language: Language) -> Result<VocabularyData, Box<dyn std::error::Error>> {
+    fn load_and_sync_vocabulary(language: Language, max_level: Option<u8>) -> Result<VocabularyData, Box<dyn std::error::Error>> {
         let vocab_path = Path::new("vocabulary.json");
-        let raw_data_path = "morphemes.csv"; 
+        let raw_data_path = "morphemes.csv";
 
         // 1. 既存のJSONデータを読み込む
         let mut all_vocabs = if vocab_path.exists() {
@@ -206,18 +1044,23 @@ impl LinguisticAssembler {
             Self::get_default_all_vocabs()
         };
 
-        // 2. CSVファイルがあれば解析
+        // 2. CSVファイルがあれば解析 (UniDic形式の日本語/英語コーパス)
         if Path::new(raw_data_path).exists() {
             println!("[Sync] '{}' を検出しました。統合を開始します...", raw_data_path);
             let extracted = MorphemeProcessor::process_file(raw_data_path)?;
-            
+
             let total_extracted = extracted.nouns.len() + extracted.particles.len() + extracted.verbs.len() + extracted.adverbs.len();
-            
+
             if total_extracted > 0 {
                 match language {
+                    #[cfg(feature = "lang-ja")]
                     Language::Japanese => all_vocabs.japanese.merge(extracted),
+                    #[cfg(feature = "lang-en")]
                     Language::English => all_vocabs.english.merge(extracted),
+                    #[cfg(feature = "lang-zh")]
                     Language::Chinese => all_vocabs.chinese.merge(extracted),
+                    #[allow(unreachable_patterns)]
+                    _ => println!("[Warning] 言語パックが無効化されているため、抽出した語彙を統合できませんでした。"),
                 }
 
                 let json = serde_json::to_string_pretty(&all_vocabs)?;
@@ -227,55 +1070,203 @@ impl LinguisticAssembler {
                 println!("[Warning] ファイルは見つかりましたが、有効な語彙を抽出できませんでした。フォーマットを確認してください。");
             }
         } else {
-            // ファイルパスのデバッグ表示
-            let current_dir = std::env::current_dir()?;
-            println!("[Debug] '{}' が見つかりません。現在のディレクトリ: {:?}", raw_data_path, current_dir);
+            // 言語ごとの代替同期パス（中国語の分かち書き／英語のBPE学習）。どちらも
+            // 対象コーパスが無いか、対応する言語パックが無効なら何もせず false を返す。
+            let synced = Self::sync_chinese_corpus(&mut all_vocabs, vocab_path, language)?
+                || Self::sync_english_corpus(&mut all_vocabs, vocab_path, language)?;
+            if !synced {
+                // ファイルパスのデバッグ表示
+                let current_dir = std::env::current_dir()?;
+                println!("[Debug] '{}' が見つかりません。現在のディレクトリ: {:?}", raw_data_path, current_dir);
+            }
         }
 
-        let target_vocab = match language {
-            Language::English => all_vocabs.english,
-            Language::Japanese => all_vocabs.japanese,
-            Language::Chinese => all_vocabs.chinese,
-        };
+        let mut target_vocab = all_vocabs.take(language)?;
+        // TextRankスコアが付与済みであっても、デフォルト語彙のみの場合は未ソートのことがあるため毎回整える
+        target_vocab.sort_by_salience();
+
+        // 3. 等級ファイルがあれば難易度を付与し、必要に応じて上限を超える単語を絞り込む
+        let grader = DifficultyGrader::load("kanji_grades.csv", "jlpt_vocab.csv")?;
+        target_vocab.annotate_levels(&grader);
+        if let Some(max_level) = max_level {
+            target_vocab.filter_by_level(max_level);
+        }
 
         Ok(target_vocab)
     }
 
+    /// 中国語はUniDic形式のカラムを持たないため、未分割テキストを分かち書きする。
+    /// `lang-zh` が無効な場合は何もせず `Ok(false)` を返す。
+    #[cfg(feature = "lang-zh")]
+    fn sync_chinese_corpus(all_vocabs: &mut LanguageVocabularies, vocab_path: &Path, language: Language) -> Result<bool, Box<dyn std::error::Error>> {
+        const RAW_CHINESE_PATH: &str = "chinese_corpus.txt";
+        if !matches!(language, Language::Chinese) || !Path::new(RAW_CHINESE_PATH).exists() {
+            return Ok(false);
+        }
+
+        println!("[Sync] '{}' を検出しました。分かち書きを開始します...", RAW_CHINESE_PATH);
+        let extracted = MorphemeProcessor::process_chinese_text(RAW_CHINESE_PATH)?;
+        let total_extracted = extracted.nouns.len() + extracted.particles.len() + extracted.verbs.len() + extracted.adverbs.len();
+
+        if total_extracted > 0 {
+            all_vocabs.chinese.merge(extracted);
+            let json = serde_json::to_string_pretty(&all_vocabs)?;
+            File::create(vocab_path)?.write_all(json.as_bytes())?;
+            println!("[Sync] 新たに {} 種類の語彙を統合・永続化しました。", total_extracted);
+        } else {
+            println!("[Warning] ファイルは見つかりましたが、有効な語彙を抽出できませんでした。フォーマットを確認してください。");
+        }
+        Ok(true)
+    }
+    #[cfg(not(feature = "lang-zh"))]
+    fn sync_chinese_corpus(_all_vocabs: &mut LanguageVocabularies, _vocab_path: &Path, _language: Language) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(false)
+    }
+
+    /// 英語はUniDicのようなカラム付きコーパスが無いことが多いため、生テキストからBPEで語彙を学習する。
+    /// `lang-en` が無効な場合は何もせず `Ok(false)` を返す。
+    #[cfg(feature = "lang-en")]
+    fn sync_english_corpus(all_vocabs: &mut LanguageVocabularies, vocab_path: &Path, language: Language) -> Result<bool, Box<dyn std::error::Error>> {
+        const RAW_ENGLISH_PATH: &str = "english_corpus.txt";
+        if !matches!(language, Language::English) || !Path::new(RAW_ENGLISH_PATH).exists() {
+            return Ok(false);
+        }
+
+        println!("[Sync] '{}' を検出しました。BPEで語彙を学習します...", RAW_ENGLISH_PATH);
+        let raw_text = fs::read_to_string(RAW_ENGLISH_PATH)?;
+        let extracted = BpeVocabBuilder::new(200, 2).train_on_text(&raw_text);
+        let total_extracted = extracted.nouns.len();
+
+        if total_extracted > 0 {
+            all_vocabs.english.merge(extracted);
+            let json = serde_json::to_string_pretty(&all_vocabs)?;
+            File::create(vocab_path)?.write_all(json.as_bytes())?;
+            println!("[Sync] 新たに {} 種類の語彙を統合・永続化しました。", total_extracted);
+        } else {
+            println!("[Warning] ファイルは見つかりましたが、有効な語彙を抽出できませんでした。フォーマットを確認してください。");
+        }
+        Ok(true)
+    }
+    #[cfg(not(feature = "lang-en"))]
+    fn sync_english_corpus(_all_vocabs: &mut LanguageVocabularies, _vocab_path: &Path, _language: Language) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(false)
+    }
+
     fn get_default_all_vocabs() -> LanguageVocabularies {
         LanguageVocabularies {
-            english: VocabularyData { nouns: vec!["Silence".into()], particles: vec!["is".into()], verbs: vec!["drifting".into()], adverbs: vec!["Gently".into()] },
-            japanese: VocabularyData { 
-                nouns: vec!["静寂".into(), "思考".into(), "技術".into(), "日常".into()], 
-                particles: vec!["は".into(), "の".into(), "に".into()], 
-                verbs: vec!["加速する".into(), "共鳴する".into()], 
-                adverbs: vec!["徐々に".into(), "突如として".into()] 
-            },
-            chinese: VocabularyData { nouns: vec!["宁静".into()], particles: vec!["是".into()], verbs: vec!["漂流".into()], adverbs: vec!["温柔地".into()] },
+            #[cfg(feature = "lang-en")]
+            english: Self::default_english_vocab(),
+            #[cfg(feature = "lang-ja")]
+            japanese: Self::default_japanese_vocab(),
+            #[cfg(feature = "lang-zh")]
+            chinese: Self::default_chinese_vocab(),
         }
     }
 
-    fn assemble(&self, state: &HashMap<usize, f64>) -> String {
+    #[cfg(feature = "lang-en")]
+    #[allow(unused_mut)]
+    fn default_english_vocab() -> VocabularyData {
+        let mut vocab = VocabularyData::empty();
+        #[cfg(feature = "scope-common")]
+        vocab.merge(VocabularyData { nouns: vec!["Silence".into()], particles: vec!["is".into()], verbs: vec!["drifting".into()], adverbs: vec!["Gently".into()], scores: HashMap::new(), transitions: TransitionModel::default(), levels: HashMap::new(), bpe_merges: Vec::new(), proper_noun_readings: HashMap::new() });
+        #[cfg(feature = "scope-archaic")]
+        vocab.merge(VocabularyData { nouns: vec!["thou".into(), "yonder".into()], particles: vec!["doth".into()], verbs: vec!["hath spoken".into()], adverbs: vec!["whence".into()], scores: HashMap::new(), transitions: TransitionModel::default(), levels: HashMap::new(), bpe_merges: Vec::new(), proper_noun_readings: HashMap::new() });
+        vocab
+    }
+
+    #[cfg(feature = "lang-ja")]
+    #[allow(unused_mut)]
+    fn default_japanese_vocab() -> VocabularyData {
+        let mut vocab = VocabularyData::empty();
+        #[cfg(feature = "scope-common")]
+        vocab.merge(VocabularyData {
+            nouns: vec!["静寂".into(), "思考".into(), "技術".into(), "日常".into()],
+            particles: vec!["は".into(), "の".into(), "に".into()],
+            verbs: vec!["加速する".into(), "共鳴する".into()],
+            adverbs: vec!["徐々に".into(), "突如として".into()],
+            scores: HashMap::new(),
+            transitions: TransitionModel::default(),
+            levels: HashMap::new(),
+            bpe_merges: Vec::new(),
+            proper_noun_readings: HashMap::new(),
+        });
+        #[cfg(feature = "scope-archaic")]
+        vocab.merge(VocabularyData {
+            nouns: vec!["言霊".into(), "古の道".into()],
+            particles: vec!["にて".into()],
+            verbs: vec!["候ふ".into()],
+            adverbs: vec!["いと".into()],
+            scores: HashMap::new(),
+            transitions: TransitionModel::default(),
+            levels: HashMap::new(),
+            bpe_merges: Vec::new(),
+            proper_noun_readings: HashMap::new(),
+        });
+        vocab
+    }
+
+    #[cfg(feature = "lang-zh")]
+    #[allow(unused_mut)]
+    fn default_chinese_vocab() -> VocabularyData {
+        let mut vocab = VocabularyData::empty();
+        #[cfg(feature = "scope-common")]
+        vocab.merge(VocabularyData { nouns: vec!["宁静".into()], particles: vec!["是".into()], verbs: vec!["漂流".into()], adverbs: vec!["温柔地".into()], scores: HashMap::new(), transitions: TransitionModel::default(), levels: HashMap::new(), bpe_merges: Vec::new(), proper_noun_readings: HashMap::new() });
+        #[cfg(feature = "scope-archaic")]
+        vocab.merge(VocabularyData { nouns: vec!["之乎者也".into()], particles: vec!["矣".into()], verbs: vec!["曰".into()], adverbs: vec!["甚".into()], scores: HashMap::new(), transitions: TransitionModel::default(), levels: HashMap::new(), bpe_merges: Vec::new(), proper_noun_readings: HashMap::new() });
+        vocab
+    }
+
+    /// agent_state から文を合成する。
+    ///
+    /// 次元ごとの振幅を個々の単語へ直接マッピングする初期設計から、バイグラム連鎖モデルによる
+    /// サンプリングへ移行済み：`sorted` は agent_state の次元を振幅降順に並べたものだが、実際に
+    /// 使うのは `pair_count`（生成する助詞・名詞の組数）の決定のみである。シード名詞は常に
+    /// salience最上位の名詞（`nouns[0]`、振幅最大の次元に順位で対応）で、シード以降の各語は
+    /// コーパス中のバイグラム出現頻度からサンプリングする。
+    /// そのため agent_state の次元magnitudeと生成される語の対応はシード語を除き失われる。
+    fn assemble(&self, state: &HashMap<usize, f64>, rng: &mut impl Rng) -> String {
         let mut sorted: Vec<(&usize, &f64)> = state.iter().collect();
         sorted.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap());
 
         let mut output = String::new();
-        let intensity = sorted[0].1.abs();
-        
-        if self.vocab.adverbs.is_empty() || self.vocab.nouns.is_empty() {
+
+        if self.vocab.adverbs.is_empty() || self.vocab.nouns.is_empty()
+            || self.vocab.particles.is_empty() || self.vocab.verbs.is_empty() {
             return "語彙が不足しています。".to_string();
         }
 
-        let adv_idx = (intensity * 100.0) as usize;
-        output.push_str(&self.vocab.adverbs[adv_idx % self.vocab.adverbs.len()]);
+        // self.vocab の各カテゴリはTextRankスコアの降順に並んでいるため、
+        // agent_state の中で最も絶対値の大きい次元に、salience最上位の名詞をシード語として割り当てる。
+        // 以降の語（助詞・名詞・動詞）は、直前の語に続いてコーパス中で実際に出現したバイグラムから選ぶ。
+        let adverb = self.vocab.adverbs[0].clone();
+        output.push_str(&adverb);
         output.push_str(if matches!(self.language, Language::English) { ", " } else { "、" });
 
-        for i in 0..3 {
-            let (&dim, _) = sorted[i];
-            output.push_str(&self.vocab.nouns[dim % self.vocab.nouns.len()]);
-            output.push_str(&self.vocab.particles[(dim + i) % self.vocab.particles.len()]);
+        let seed_noun = self.vocab.nouns[0].clone();
+        output.push_str(&seed_noun);
+
+        let mut prev = seed_noun;
+        let pair_count = sorted.len().clamp(1, 3);
+        for i in 0..pair_count {
+            let particle = self.vocab.transitions.sample_next(&prev, &self.vocab.particles, rng)
+                .or_else(|| self.vocab.transitions.weighted_start("particle", &self.vocab.particles, rng))
+                .unwrap_or_else(|| self.vocab.particles[i % self.vocab.particles.len()].clone());
+            output.push_str(&particle);
+            prev = particle;
+
+            if i + 1 < pair_count {
+                let noun = self.vocab.transitions.sample_next(&prev, &self.vocab.nouns, rng)
+                    .or_else(|| self.vocab.transitions.weighted_start("noun", &self.vocab.nouns, rng))
+                    .unwrap_or_else(|| self.vocab.nouns[(i + 1) % self.vocab.nouns.len()].clone());
+                output.push_str(&noun);
+                prev = noun;
+            }
         }
-        
-        output.push_str(&self.vocab.verbs[*sorted[0].0 % self.vocab.verbs.len()]);
+
+        let verb = self.vocab.transitions.sample_next(&prev, &self.vocab.verbs, rng)
+            .or_else(|| self.vocab.transitions.weighted_start("verb", &self.vocab.verbs, rng))
+            .unwrap_or_else(|| self.vocab.verbs[0].clone());
+        output.push_str(&verb);
         output.push_str(if matches!(self.language, Language::English) { "." } else { "。" });
         output
     }
@@ -294,7 +1285,7 @@ fn calculate_variance(state: &HashMap<usize, f64>) -> f64 {
 
 fn run_experiment(config: ExperimentConfig, lang: Language) -> Result<ResearchReport, Box<dyn std::error::Error>> {
     let mut integrator = EverlingIntegrator::new(config.active_dimensions);
-    let assembler = LinguisticAssembler::new(lang)?;
+    let assembler = LinguisticAssembler::new(lang, config.max_level)?;
     let mut rng = rand::thread_rng();
 
     let mut hasher = DefaultHasher::new();
@@ -327,12 +1318,30 @@ fn run_experiment(config: ExperimentConfig, lang: Language) -> Result<ResearchRe
         }
     }
 
+    let generated_sentence = assembler.assemble(&agent_state, &mut rng);
+
+    let audio_path = if config.synthesize_audio && matches!(lang, Language::Japanese) {
+        fs::create_dir_all("results")?;
+        let path = format!("results/speech_{}.wav", config.speaker_id);
+        let mut synthesizer = SpeechSynthesizer::new(Box::new(SilentWavBackend));
+        // MorphemeProcessorがコーパスから抽出した固有名詞の読みをユーザー辞書に登録する
+        for (surface, reading) in &assembler.vocab.proper_noun_readings {
+            synthesizer.register_reading(surface, reading);
+        }
+        synthesizer.synthesize_to_file(&generated_sentence, config.speaker_id, Path::new(&path))?;
+        println!("[Audio] '{}' に音声を書き出しました。", path);
+        Some(path)
+    } else {
+        None
+    };
+
     Ok(ResearchReport {
         config: config.clone(),
         metrics,
-        generated_sentence: assembler.assemble(&agent_state),
+        generated_sentence,
         variance_change: calculate_variance(&agent_state) / initial_variance.max(1e-9),
         intensity_score: agent_state.values().map(|v| v.abs()).sum::<f64>() / config.active_dimensions as f64,
+        audio_path,
     })
 }
 
@@ -344,6 +1353,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     io::stdin().read_line(&mut seed_text)?;
     let seed_text = seed_text.trim().to_string();
 
+    println!("\n語彙レベルの上限(JLPT N5=1 ... N1=5)を指定しますか? 指定しない場合は空欄のままEnter:");
+    let mut max_level_input = String::new();
+    io::stdin().read_line(&mut max_level_input)?;
+    let max_level = max_level_input.trim().parse::<u8>().ok();
+
+    println!("\n音声合成を行いますか? (y/n):");
+    let mut synthesize_input = String::new();
+    io::stdin().read_line(&mut synthesize_input)?;
+    let synthesize_audio = matches!(synthesize_input.trim(), "y" | "Y" | "yes");
+
+    let speaker_id = if synthesize_audio {
+        println!("\n話者IDを入力してください:");
+        let mut speaker_input = String::new();
+        io::stdin().read_line(&mut speaker_input)?;
+        speaker_input.trim().parse::<u32>().unwrap_or(1)
+    } else {
+        1
+    };
+
     fs::create_dir_all("results")?;
 
     let config = ExperimentConfig {
@@ -354,6 +1382,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         noise_scale: 0.05,
         alpha: 0.95,
         seed_text: seed_text.clone(),
+        max_level,
+        synthesize_audio,
+        speaker_id,
     };
 
     let report = run_experiment(config, Language::Japanese)?;